@@ -1,50 +1,96 @@
-use crate::{enums::ObjectVersionUE5, Error, ObjectVersion, Result};
-use binread::BinReaderExt;
+use crate::compression::{decompress_chunks, ChunkDecompressor, CompressedChunkInfo, DecompressedReader};
+use crate::{enums::ObjectVersionUE5, guid::Guid, Error, ObjectVersion, Result};
+use binread::{BinReaderExt, Endian};
 use num_traits::FromPrimitive;
-use std::io::{Read, Seek};
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Seek};
 
 /// Magic sequence identifying an unreal asset (can also be used to determine endianness)
 const PACKAGE_FILE_MAGIC: u32 = 0x9E2A83C1;
 
+/// Controls how tolerant [`Archive::with_options`] is of data it doesn't fully recognize.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArchiveOptions {
+    /// When `true`, a nonzero `file_version`/`file_version_ue5` that `ObjectVersion`/`ObjectVersionUE5`
+    /// doesn't recognize is kept around as a raw `i32` instead of failing to open the asset.
+    /// A `file_version` of 0 is still rejected with `UnsupportedUE4Version`.
+    /// Defaults to `false`, matching [`Archive::new`].
+    pub lenient: bool,
+}
+
+impl ArchiveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lenient(mut self, lenient: bool) -> Self {
+        self.lenient = lenient;
+        self
+    }
+}
+
 #[derive(Debug)]
 pub struct Archive<R> {
     pub reader: R,
-    /// The serialization version used when saving this asset (C++ name: `FileVersionUE4`)
+    /// The byte order the package was saved with, detected from `PACKAGE_FILE_MAGIC`
+    pub endian: Endian,
+    /// The serialization version used when saving this asset (C++ name: `FileVersionUE4`). In
+    /// lenient mode, if the raw version wasn't recognized this is set to
+    /// `ObjectVersion::VER_UE4_AUTOMATIC_VERSION` as a safe upper-bound placeholder; prefer
+    /// [`Self::serialized_with`] over comparing this field directly, since it also consults
+    /// [`Self::raw_file_version`].
     pub file_version: ObjectVersion,
+    /// The raw `FileVersionUE4` value, kept when [`ArchiveOptions::lenient`] is set and the
+    /// value didn't match a known `ObjectVersion`.
+    pub raw_file_version: Option<i32>,
     /// The serialization version used when saving this asset (C++ name: `FileVersionUE5`)
     pub file_version_ue5: Option<ObjectVersionUE5>,
+    /// The raw `FileVersionUE5` value, kept when [`ArchiveOptions::lenient`] is set and the
+    /// value didn't match a known `ObjectVersionUE5`.
+    pub raw_file_version_ue5: Option<i32>,
     /// The licensee serialization version used when saving this asset (C++ name: `FileVersionLicenseeUE4`)
     pub file_licensee_version: i32,
     pub legacy_version: i32,
+    /// Per-plugin/module versions that gate serialization independently of `file_version`
+    /// (C++ name: `FCustomVersionContainer`)
+    pub custom_versions: HashMap<Guid, i32>,
 }
 
 impl<R> Archive<R>
 where
     R: Seek + Read,
 {
-    pub fn new(mut reader: R) -> Result<Self> {
+    pub fn new(reader: R) -> Result<Self> {
+        Self::with_options(reader, ArchiveOptions::default())
+    }
+
+    pub fn with_options(mut reader: R, options: ArchiveOptions) -> Result<Self> {
         let magic: u32 = reader.read_le()?;
-        if magic != PACKAGE_FILE_MAGIC {
+        let endian = if magic == PACKAGE_FILE_MAGIC {
+            Endian::Little
+        } else if magic == PACKAGE_FILE_MAGIC.swap_bytes() {
+            Endian::Big
+        } else {
             return Err(Error::InvalidFile);
-        }
+        };
 
         // See `void operator<<(FStructuredArchive::FSlot Slot, FPackageFileSummary& Sum)` in Engine/Source/Runtime/CoreUObject/Private/UObject/PackageFileSummary.cpp
-        let legacy_version: i32 = reader.read_le()?;
+        let legacy_version: i32 = reader.read_type(endian)?;
         if !(-8..=-6).contains(&legacy_version) {
             return Err(Error::UnsupportedVersion(legacy_version));
         }
 
-        let _legacy_ue3_version: i32 = reader.read_le()?;
+        let _legacy_ue3_version: i32 = reader.read_type(endian)?;
 
-        let file_version = reader.read_le()?;
+        let file_version = reader.read_type(endian)?;
 
         let file_version_ue5 = if legacy_version <= -8 {
-            reader.read_le()?
+            reader.read_type(endian)?
         } else {
             0
         };
 
-        let file_licensee_version: i32 = reader.read_le()?;
+        let file_licensee_version: i32 = reader.read_type(endian)?;
         if file_version == 0 && file_licensee_version == 0 && file_version_ue5 == 0 {
             return Err(Error::UnversionedAsset);
         }
@@ -52,24 +98,50 @@ where
         if file_version == 0 {
             return Err(Error::UnsupportedUE4Version(file_version));
         }
-        let file_version = ObjectVersion::from_i32(file_version)
-            .ok_or(Error::UnsupportedUE4Version(file_version))?;
-
-        let file_version_ue5 = if file_version_ue5 != 0 {
-            Some(
-                ObjectVersionUE5::from_i32(file_version_ue5)
-                    .ok_or(Error::UnsupportedUE5Version(file_version_ue5))?,
-            )
+        let (file_version, raw_file_version) = match ObjectVersion::from_i32(file_version) {
+            Some(file_version) => (file_version, None),
+            None if options.lenient => {
+                (ObjectVersion::VER_UE4_AUTOMATIC_VERSION, Some(file_version))
+            }
+            None => return Err(Error::UnsupportedUE4Version(file_version)),
+        };
+
+        let (file_version_ue5, raw_file_version_ue5) = if file_version_ue5 != 0 {
+            match ObjectVersionUE5::from_i32(file_version_ue5) {
+                Some(file_version_ue5) => (Some(file_version_ue5), None),
+                None if options.lenient => (None, Some(file_version_ue5)),
+                None => return Err(Error::UnsupportedUE5Version(file_version_ue5)),
+            }
         } else {
-            None
+            (None, None)
         };
 
+        // See `void FCustomVersionContainer::Serialize()` in
+        // Engine/Source/Runtime/Core/Private/Serialization/CustomVersion.cpp. The only
+        // serialization format reachable from the legacy versions accepted above is the
+        // "optimized" one: a plain array of (FGuid key, int32 version) pairs, with the
+        // friendly name looked up from the registered custom version rather than serialized.
+        // Not pre-reserving here: `custom_versions_num` is an untrusted i32 read straight off
+        // disk, and reserving from it would let a corrupt or byte-swapped count trigger a
+        // multi-gigabyte allocation before a single entry is actually read.
+        let custom_versions_num: i32 = reader.read_type(endian)?;
+        let mut custom_versions = HashMap::new();
+        for _ in 0..custom_versions_num {
+            let key: Guid = reader.read_type(endian)?;
+            let version: i32 = reader.read_type(endian)?;
+            custom_versions.insert(key, version);
+        }
+
         Ok(Archive {
             reader,
+            endian,
             file_version,
+            raw_file_version,
             file_version_ue5,
+            raw_file_version_ue5,
             file_licensee_version,
             legacy_version,
+            custom_versions,
         })
     }
 
@@ -78,12 +150,80 @@ where
     }
 
     pub fn serialized_with(&self, version: ObjectVersion) -> bool {
-        self.file_version >= version
+        match self.raw_file_version {
+            Some(raw) => raw >= version as i32,
+            None => self.file_version >= version,
+        }
     }
 
     pub fn serialized_without(&self, version: ObjectVersion) -> bool {
         !self.serialized_with(version)
     }
+
+    /// Returns whether this asset is from UE5, i.e. it carries a `file_version_ue5`.
+    pub fn is_ue5(&self) -> bool {
+        self.file_version_ue5.is_some() || self.raw_file_version_ue5.is_some()
+    }
+
+    /// Like [`Self::serialized_with`] but for the UE5 version axis. A pre-UE5 asset (one
+    /// without a `file_version_ue5`) predates every UE5 gate, so this returns `false`.
+    pub fn serialized_with_ue5(&self, version: ObjectVersionUE5) -> bool {
+        match self.raw_file_version_ue5 {
+            Some(raw) => raw >= version as i32,
+            None => self.file_version_ue5.map_or(false, |v| v >= version),
+        }
+    }
+
+    /// Like [`Self::serialized_without`] but for the UE5 version axis. A pre-UE5 asset (one
+    /// without a `file_version_ue5`) predates every UE5 gate, so this returns `true`.
+    pub fn serialized_without_ue5(&self, version: ObjectVersionUE5) -> bool {
+        !self.serialized_with_ue5(version)
+    }
+
+    /// Looks up the version this asset was saved with for a given custom version `guid`.
+    pub fn custom_version(&self, guid: Guid) -> Option<i32> {
+        self.custom_versions.get(&guid).copied()
+    }
+
+    /// Returns whether this asset was saved with at least `version` of the custom version
+    /// identified by `guid`. Assets that don't carry the custom version at all return `false`.
+    pub fn serialized_with_custom(&self, guid: Guid, version: i32) -> bool {
+        self.custom_version(guid).map_or(false, |v| v >= version)
+    }
+
+    /// Replaces this archive's backing reader with a fully decompressed, contiguous view
+    /// built from `chunks`, so the rest of the crate never has to deal with compression.
+    /// Use [`crate::compression::DefaultChunkDecompressor`] for Zlib/Gzip/LZ4 packages, or
+    /// supply a custom [`ChunkDecompressor`] for proprietary formats such as Oodle.
+    ///
+    /// `chunks` and `method` are deliberately caller-supplied rather than read off `reader`
+    /// automatically. `CompressionFlags`/`CompressedChunks` sit near the end of
+    /// `FPackageFileSummary`, after `FolderName`, `PackageFlags`, the name/export/import/depends
+    /// tables, the package `Guid`, the `Generations` array and two `FEngineVersion` structs —
+    /// none of which this crate parses today. Guessing at that layout without fixtures to
+    /// validate against would risk silently misreading the chunk table instead of failing
+    /// loudly, which is worse than asking the caller for it. Once the rest of the summary is
+    /// parsed, a summary-driven constructor can be added alongside this one; until then, this is
+    /// the supported entry point.
+    pub fn into_decompressed(
+        mut self,
+        chunks: &[CompressedChunkInfo],
+        method: u32,
+        decompressor: &dyn ChunkDecompressor,
+    ) -> Result<Archive<DecompressedReader>> {
+        let bytes = decompress_chunks(&mut self.reader, chunks, method, decompressor)?;
+        Ok(Archive {
+            reader: Cursor::new(bytes),
+            endian: self.endian,
+            file_version: self.file_version,
+            raw_file_version: self.raw_file_version,
+            file_version_ue5: self.file_version_ue5,
+            raw_file_version_ue5: self.raw_file_version_ue5,
+            file_licensee_version: self.file_licensee_version,
+            legacy_version: self.legacy_version,
+            custom_versions: self.custom_versions,
+        })
+    }
 }
 
 impl<R> Read for Archive<R>
@@ -103,3 +243,58 @@ where
         self.reader.seek(pos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Builds a minimal little-endian summary prefix: magic, legacy/UE3 versions, a UE4 file
+    /// version that won't resolve to a known `ObjectVersion` (so the test doesn't depend on
+    /// any particular enum discriminant), no UE5 version, and the given custom versions.
+    fn summary_bytes(custom_versions: &[(Guid, i32)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&PACKAGE_FILE_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&(-7i32).to_le_bytes()); // legacy_version: no UE5 axis
+        bytes.extend_from_slice(&0i32.to_le_bytes()); // legacy_ue3_version
+        bytes.extend_from_slice(&9999i32.to_le_bytes()); // file_version (unrecognized)
+        bytes.extend_from_slice(&0i32.to_le_bytes()); // file_licensee_version
+        bytes.extend_from_slice(&(custom_versions.len() as i32).to_le_bytes());
+        for (guid, version) in custom_versions {
+            bytes.extend_from_slice(&guid.a.to_le_bytes());
+            bytes.extend_from_slice(&guid.b.to_le_bytes());
+            bytes.extend_from_slice(&guid.c.to_le_bytes());
+            bytes.extend_from_slice(&guid.d.to_le_bytes());
+            bytes.extend_from_slice(&version.to_le_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn parses_custom_version_container_with_multiple_entries() {
+        let guid_a = Guid { a: 1, b: 2, c: 3, d: 4 };
+        let guid_b = Guid { a: 5, b: 6, c: 7, d: 8 };
+        let bytes = summary_bytes(&[(guid_a, 10), (guid_b, 20)]);
+
+        let archive =
+            Archive::with_options(Cursor::new(bytes), ArchiveOptions::new().lenient(true)).unwrap();
+
+        assert_eq!(archive.custom_versions.len(), 2);
+        assert_eq!(archive.custom_version(guid_a), Some(10));
+        assert_eq!(archive.custom_version(guid_b), Some(20));
+        assert_eq!(archive.custom_version(Guid::default()), None);
+        assert!(archive.serialized_with_custom(guid_a, 10));
+        assert!(!archive.serialized_with_custom(guid_a, 11));
+        assert!(!archive.serialized_with_custom(Guid::default(), 0));
+    }
+
+    #[test]
+    fn parses_empty_custom_version_container() {
+        let bytes = summary_bytes(&[]);
+
+        let archive =
+            Archive::with_options(Cursor::new(bytes), ArchiveOptions::new().lenient(true)).unwrap();
+
+        assert!(archive.custom_versions.is_empty());
+    }
+}
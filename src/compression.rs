@@ -0,0 +1,250 @@
+use crate::{Error, Result};
+use binread::BinRead;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+/// Describes one compressed block of a package (C++ name: `FCompressedChunkInfo`)
+#[derive(BinRead, Debug, Clone, Copy)]
+pub struct CompressedChunkInfo {
+    pub uncompressed_offset: i64,
+    pub uncompressed_size: i64,
+    pub compressed_offset: i64,
+    pub compressed_size: i64,
+}
+
+/// Decompresses a single chunk for a given compression method. Implement this to plug in a
+/// backend the crate doesn't ship itself, such as Oodle, which is proprietary and can't be
+/// bundled here.
+pub trait ChunkDecompressor {
+    fn decompress(&self, method: u32, compressed: &[u8], uncompressed_size: usize) -> Result<Vec<u8>>;
+}
+
+/// Well-known compression methods, matching the historical `ECompressionFlags` bits used by
+/// `FPackageFileSummary::CompressionFlags`.
+pub const COMPRESS_ZLIB: u32 = 1;
+pub const COMPRESS_GZIP: u32 = 2;
+pub const COMPRESS_LZ4: u32 = 4;
+
+/// [`ChunkDecompressor`] covering the open formats the crate can decode without an external
+/// licensed library: Zlib, Gzip and LZ4. Any other method, notably Oodle, is reported via
+/// [`Error::UnsupportedCompression`] so a caller can supply its own [`ChunkDecompressor`].
+#[derive(Debug, Default)]
+pub struct DefaultChunkDecompressor;
+
+impl ChunkDecompressor for DefaultChunkDecompressor {
+    fn decompress(&self, method: u32, compressed: &[u8], uncompressed_size: usize) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(uncompressed_size);
+        match method {
+            COMPRESS_ZLIB => {
+                flate2::read::ZlibDecoder::new(compressed).read_to_end(&mut out)?;
+            }
+            COMPRESS_GZIP => {
+                flate2::read::GzDecoder::new(compressed).read_to_end(&mut out)?;
+            }
+            COMPRESS_LZ4 => {
+                out = lz4_flex::decompress(compressed, uncompressed_size)
+                    .map_err(|_| Error::DecompressionFailed(method))?;
+            }
+            _ => return Err(Error::UnsupportedCompression(method)),
+        }
+        Ok(out)
+    }
+}
+
+/// A single chunk can't plausibly decompress past this size; Unreal's own compressed chunks are
+/// capped at `LOADING_COMPRESSION_CHUNK_SIZE`-sized blocks (a few hundred KiB at most), so this
+/// is generous headroom while still turning a hostile `uncompressed_size` like `i64::MAX` into
+/// an `Err` instead of an eager multi-exabyte allocation.
+const MAX_CHUNK_UNCOMPRESSED_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Reads every chunk of `chunks` out of `reader`, decompresses it with `decompressor` and
+/// stitches the results back into one contiguous buffer addressed by `uncompressed_offset`, so
+/// the rest of the crate can parse a package as if it had never been compressed.
+pub fn decompress_chunks<R: Read + Seek>(
+    reader: &mut R,
+    chunks: &[CompressedChunkInfo],
+    method: u32,
+    decompressor: &dyn ChunkDecompressor,
+) -> Result<Vec<u8>> {
+    // The compressed side of every chunk has to live somewhere inside `reader`, so its size is
+    // bounded by the stream's actual length; that catches a corrupt/hostile `compressed_size`
+    // before it drives an oversized `vec![0u8; ..]` below.
+    let stream_len = reader.seek(SeekFrom::End(0))?;
+
+    let mut total_size: usize = 0;
+    for chunk in chunks {
+        if chunk.uncompressed_offset < 0
+            || chunk.uncompressed_size < 0
+            || chunk.compressed_offset < 0
+            || chunk.compressed_size < 0
+        {
+            return Err(Error::InvalidCompressedChunk(*chunk));
+        }
+        if chunk.uncompressed_size as u64 > MAX_CHUNK_UNCOMPRESSED_SIZE {
+            return Err(Error::InvalidCompressedChunk(*chunk));
+        }
+        (chunk.compressed_offset as u64)
+            .checked_add(chunk.compressed_size as u64)
+            .filter(|&end| end <= stream_len)
+            .ok_or(Error::InvalidCompressedChunk(*chunk))?;
+
+        let chunk_end = chunk
+            .uncompressed_offset
+            .checked_add(chunk.uncompressed_size)
+            .and_then(|end| usize::try_from(end).ok())
+            .ok_or(Error::InvalidCompressedChunk(*chunk))?;
+        total_size = total_size.max(chunk_end);
+    }
+    let mut out = vec![0u8; total_size];
+
+    for chunk in chunks {
+        let compressed_size = usize::try_from(chunk.compressed_size)
+            .map_err(|_| Error::InvalidCompressedChunk(*chunk))?;
+        let uncompressed_size = usize::try_from(chunk.uncompressed_size)
+            .map_err(|_| Error::InvalidCompressedChunk(*chunk))?;
+        let start = usize::try_from(chunk.uncompressed_offset)
+            .map_err(|_| Error::InvalidCompressedChunk(*chunk))?;
+
+        reader.seek(SeekFrom::Start(chunk.compressed_offset as u64))?;
+        let mut compressed = vec![0u8; compressed_size];
+        reader.read_exact(&mut compressed)?;
+
+        let decompressed = decompressor.decompress(method, &compressed, uncompressed_size)?;
+        if decompressed.len() != uncompressed_size {
+            return Err(Error::CompressedChunkSizeMismatch {
+                expected: uncompressed_size,
+                actual: decompressed.len(),
+            });
+        }
+
+        out[start..start + decompressed.len()].copy_from_slice(&decompressed);
+    }
+
+    Ok(out)
+}
+
+/// A `Read + Seek` view over a package's fully decompressed bytes.
+pub type DecompressedReader = Cursor<Vec<u8>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn zlib_compress(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decompresses_multiple_chunks_in_order() {
+        let first = b"hello ".repeat(4);
+        let second = b"world!".repeat(4);
+        let first_compressed = zlib_compress(&first);
+        let second_compressed = zlib_compress(&second);
+
+        let mut input = Vec::new();
+        input.extend_from_slice(&first_compressed);
+        let second_offset = input.len();
+        input.extend_from_slice(&second_compressed);
+
+        let chunks = vec![
+            CompressedChunkInfo {
+                uncompressed_offset: 0,
+                uncompressed_size: first.len() as i64,
+                compressed_offset: 0,
+                compressed_size: first_compressed.len() as i64,
+            },
+            CompressedChunkInfo {
+                uncompressed_offset: first.len() as i64,
+                uncompressed_size: second.len() as i64,
+                compressed_offset: second_offset as i64,
+                compressed_size: second_compressed.len() as i64,
+            },
+        ];
+
+        let mut reader = Cursor::new(input);
+        let out = decompress_chunks(&mut reader, &chunks, COMPRESS_ZLIB, &DefaultChunkDecompressor).unwrap();
+
+        let mut expected = first;
+        expected.extend_from_slice(&second);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn truncated_chunk_is_an_error() {
+        let compressed = zlib_compress(b"hello world");
+        let chunk = CompressedChunkInfo {
+            uncompressed_offset: 0,
+            uncompressed_size: 11,
+            compressed_offset: 0,
+            // Claims more compressed bytes than are actually in the stream.
+            compressed_size: compressed.len() as i64 + 64,
+        };
+
+        let mut reader = Cursor::new(compressed);
+        let result = decompress_chunks(&mut reader, &[chunk], COMPRESS_ZLIB, &DefaultChunkDecompressor);
+        assert!(matches!(result, Err(Error::InvalidCompressedChunk(_))));
+    }
+
+    #[test]
+    fn oversized_decompress_is_an_error_not_a_panic() {
+        let data = b"hello world";
+        let compressed = zlib_compress(data);
+        let chunk = CompressedChunkInfo {
+            uncompressed_offset: 0,
+            // Declares a smaller size than the chunk actually decompresses to.
+            uncompressed_size: (data.len() - 1) as i64,
+            compressed_offset: 0,
+            compressed_size: compressed.len() as i64,
+        };
+
+        let mut reader = Cursor::new(compressed);
+        let result = decompress_chunks(&mut reader, &[chunk], COMPRESS_ZLIB, &DefaultChunkDecompressor);
+        assert!(matches!(result, Err(Error::CompressedChunkSizeMismatch { .. })));
+    }
+
+    #[test]
+    fn negative_offsets_are_rejected_before_allocating() {
+        let chunk = CompressedChunkInfo {
+            uncompressed_offset: -1,
+            uncompressed_size: i64::MAX,
+            compressed_offset: 0,
+            compressed_size: 0,
+        };
+
+        let mut reader = Cursor::new(Vec::new());
+        let result = decompress_chunks(&mut reader, &[chunk], COMPRESS_ZLIB, &DefaultChunkDecompressor);
+        assert!(matches!(result, Err(Error::InvalidCompressedChunk(_))));
+    }
+
+    #[test]
+    fn implausibly_large_uncompressed_size_is_rejected_before_allocating() {
+        // Non-negative offset, so this exercises the magnitude cap rather than the sign check.
+        let chunk = CompressedChunkInfo {
+            uncompressed_offset: 0,
+            uncompressed_size: i64::MAX,
+            compressed_offset: 0,
+            compressed_size: 0,
+        };
+
+        let mut reader = Cursor::new(Vec::new());
+        let result = decompress_chunks(&mut reader, &[chunk], COMPRESS_ZLIB, &DefaultChunkDecompressor);
+        assert!(matches!(result, Err(Error::InvalidCompressedChunk(_))));
+    }
+
+    #[test]
+    fn oversized_compressed_size_is_rejected_against_the_stream_length() {
+        let chunk = CompressedChunkInfo {
+            uncompressed_offset: 0,
+            uncompressed_size: 0,
+            compressed_offset: 0,
+            // Claims far more compressed bytes than the (empty) stream actually has.
+            compressed_size: i64::MAX,
+        };
+
+        let mut reader = Cursor::new(Vec::new());
+        let result = decompress_chunks(&mut reader, &[chunk], COMPRESS_ZLIB, &DefaultChunkDecompressor);
+        assert!(matches!(result, Err(Error::InvalidCompressedChunk(_))));
+    }
+}
@@ -14,6 +14,14 @@ pub enum Error {
     ParseError(binread::Error),
     #[error("failed to seek in stream: {0:?}")]
     IoError(std::io::Error),
+    #[error("no decompressor registered for compression method {0:#x}")]
+    UnsupportedCompression(u32),
+    #[error("failed to decompress chunk data for compression method {0:#x}")]
+    DecompressionFailed(u32),
+    #[error("chunk decompressed to {actual} bytes, expected {expected}")]
+    CompressedChunkSizeMismatch { expected: usize, actual: usize },
+    #[error("chunk has an invalid offset or size: {0:?}")]
+    InvalidCompressedChunk(crate::compression::CompressedChunkInfo),
 }
 
 impl From<binread::Error> for Error {
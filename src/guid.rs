@@ -0,0 +1,10 @@
+use binread::BinRead;
+
+/// A 128-bit globally unique identifier (C++ name: `FGuid`)
+#[derive(BinRead, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Guid {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}